@@ -0,0 +1,81 @@
+//! A memoizing wrapper that turns a [DhCache] plus a closure into a self-populating function,
+//! following Polars' `FastCachedFunc`: call it with `k`, and it computes `v` on a miss, stores it,
+//! and returns `&V` either way - never more than once per distinct `k` live in the cache.
+
+use crate::shared_hash::{DhCache, Entry};
+use crate::InsertionIndex;
+use core::hash::Hash;
+use hash_injector::Flags;
+
+pub struct CachedFn<
+    K,
+    V,
+    Func,
+    I: InsertionIndex,
+    const MOST_RECENT_FAST: bool = false,
+    const RECYCLE: bool = false,
+    const F: Flags = { Flags::EMPTY },
+> {
+    cache: DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>,
+    func: Func,
+}
+
+impl<
+        K: Hash + Eq,
+        V,
+        Func: FnMut(&K) -> V,
+        I: InsertionIndex,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+        const F: Flags,
+    > CachedFn<K, V, Func, I, MOST_RECENT_FAST, RECYCLE, F>
+{
+    pub fn new(max_size: usize, func: Func) -> Self {
+        Self {
+            cache: DhCache::new(max_size),
+            func,
+        }
+    }
+
+    /// Returns the cached value for `k`, computing (and storing) it first on a miss. Hits promote
+    /// the entry to most-recently-used, same as [DhCache::get].
+    pub fn call(&mut self, k: K) -> &V {
+        match self.cache.entry(k) {
+            Entry::Occupied(occupied) => occupied.into_mut(),
+            Entry::Vacant(vacant) => {
+                let v = (self.func)(vacant.key());
+                vacant.insert(v)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn call_only_invokes_func_once_per_key() {
+        let mut calls = 0;
+        let mut cached: CachedFn<String, usize, _, u32> = CachedFn::new(2, |k: &String| {
+            calls += 1;
+            k.len()
+        });
+        assert_eq!(*cached.call("abc".to_string()), 3);
+        assert_eq!(*cached.call("abc".to_string()), 3);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn call_recomputes_after_the_entry_is_evicted() {
+        let mut calls = 0;
+        let mut cached: CachedFn<String, usize, _, u32> = CachedFn::new(1, |k: &String| {
+            calls += 1;
+            k.len()
+        });
+        cached.call("a".to_string());
+        cached.call("bb".to_string());
+        cached.call("a".to_string());
+        assert_eq!(calls, 3);
+    }
+}
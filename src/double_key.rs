@@ -41,55 +41,24 @@ impl<K> CloneKey<K> for Arc<K> {
     }
 }
 
-/**
- * Like a tuple of `I` and `CK`, but using only `I` part for comparison, so that we don't need the
- * `CK` part when looking it up.
- */
-struct IndexAndKey<K, I: InsertionIndex, CK: CloneKey<K>> {
-    idx: I,
+/// A node of the intrusive recency list, addressed by `I` (a narrow slot index instead of
+/// `usize`). `prev`/`next` are [InsertionIndex::NIL] at the list ends.
+struct Node<K, I: InsertionIndex, CK: CloneKey<K>> {
+    prev: I,
+    next: I,
     ck: CK,
     _phantom_key: PhantomData<K>,
 }
-impl<K, I: InsertionIndex, CK: CloneKey<K>> IndexAndKey<K, I, CK> {
-    fn new(idx: I, ck: CK) -> Self {
+impl<K, I: InsertionIndex, CK: CloneKey<K>> Node<K, I, CK> {
+    fn new(ck: CK) -> Self {
         Self {
-            idx,
+            prev: I::NIL,
+            next: I::NIL,
             ck,
             _phantom_key: PhantomData,
         }
     }
 }
-impl<K, I: InsertionIndex, CK: CloneKey<K>> PartialEq for IndexAndKey<K, I, CK> {
-    fn eq(&self, other: &Self) -> bool {
-        self.idx == other.idx
-    }
-    fn ne(&self, other: &Self) -> bool {
-        self.idx != other.idx
-    }
-}
-impl<K, I: InsertionIndex, CK: CloneKey<K>> Eq for IndexAndKey<K, I, CK> {}
-impl<K, I: InsertionIndex, CK: CloneKey<K>> PartialOrd for IndexAndKey<K, I, CK> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.idx.partial_cmp(&other.idx)
-    }
-    fn ge(&self, other: &Self) -> bool {
-        self.idx.ge(&other.idx)
-    }
-    fn gt(&self, other: &Self) -> bool {
-        self.idx.gt(&other.idx)
-    }
-    fn le(&self, other: &Self) -> bool {
-        self.idx.le(&other.idx)
-    }
-    fn lt(&self, other: &Self) -> bool {
-        self.idx.lt(&other.idx)
-    }
-}
-impl<K, I: InsertionIndex, CK: CloneKey<K>> Ord for IndexAndKey<K, I, CK> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.idx.cmp(&other.idx)
-    }
-}
 
 pub struct LRUCache<
     K,
@@ -100,16 +69,15 @@ pub struct LRUCache<
     const RECYCLE: bool,
 > {
     max_size: usize,
-    next_insertion_index: I,
-    //                      HashMap<  KandI,      V >
-    //
-    //                      HashMap< (K, I, u64), V >
+    //                      HashMap<  CK,        (V, slot) >
     key_to_value_and_index: HashMap<CK, (V, I)>,
-    /** Always sorted. */
-    //                Vec< Idx >
-    //
-    //                Vec< (I, u64) >
-    indexes_and_keys: Vec<IndexAndKey<K, I, CK>>,
+    /// Slab of recency-list nodes, addressed by slot (the same `I` stored alongside each value).
+    /// Touching an entry unlinks and relinks its node at `tail` in O(1); eviction pops `head`.
+    nodes: Vec<Node<K, I, CK>>,
+    /// Vacated slots available for reuse before growing `nodes`.
+    free_slots: Vec<I>,
+    head: I,
+    tail: I,
     _phantom_key: PhantomData<K>,
 }
 
@@ -126,69 +94,172 @@ impl<
         assert!(I::accommodates(max_size));
         Self {
             max_size,
-            next_insertion_index: I::ZERO,
             key_to_value_and_index: HashMap::with_capacity(max_size),
-            indexes_and_keys: Vec::with_capacity(max_size),
+            nodes: Vec::with_capacity(max_size),
+            free_slots: Vec::new(),
+            head: I::NIL,
+            tail: I::NIL,
             _phantom_key: PhantomData,
         }
     }
 
+    pub(crate) fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.key_to_value_and_index.len()
+    }
+
+    /// Unlinks the node at `slot` from wherever it currently sits in the list.
+    fn unlink(&mut self, slot: I) {
+        let (prev, next) = {
+            let node = &self.nodes[slot.to_usize()];
+            (node.prev, node.next)
+        };
+        if prev != I::NIL {
+            self.nodes[prev.to_usize()].next = next;
+        } else {
+            self.head = next;
+        }
+        if next != I::NIL {
+            self.nodes[next.to_usize()].prev = prev;
+        } else {
+            self.tail = prev;
+        }
+    }
+
+    /// Links `slot` in as the most-recently-used (the tail) of the list.
+    fn link_at_tail(&mut self, slot: I) {
+        let old_tail = self.tail;
+        {
+            let node = &mut self.nodes[slot.to_usize()];
+            node.prev = old_tail;
+            node.next = I::NIL;
+        }
+        if old_tail != I::NIL {
+            self.nodes[old_tail.to_usize()].next = slot;
+        } else {
+            self.head = slot;
+        }
+        self.tail = slot;
+    }
+
+    /// Allocates a slot for `ck`, reusing a vacated one if available.
+    fn alloc_slot(&mut self, ck: CK) -> I {
+        if let Some(slot) = self.free_slots.pop() {
+            self.nodes[slot.to_usize()] = Node::new(ck);
+            slot
+        } else {
+            let slot = I::from_usize(self.nodes.len());
+            self.nodes.push(Node::new(ck));
+            slot
+        }
+    }
+
     pub fn put(&mut self, k: K, v: V) {
         debug_assert!(self.key_to_value_and_index.len() <= self.max_size);
 
-        if let Some((_old_v, old_idx)) = self.key_to_value_and_index.remove(&k) {
-            let old_idx_and_key_pos = self
-                .indexes_and_keys
-                .binary_search_by_key(&old_idx, |idx_and_key| idx_and_key.idx)
-                .unwrap();
-            // We always remove the old entry, even if the storage is not full (to our capacity)
-            // yet. We could store an Option, and set it to None, which would save the shifting of
-            // the rest of items. However, that would help only while storage is not full. But, a
-            // cache is beneficial/intended to use once it gets full, so we keep it simple.
-            let old_key = self.indexes_and_keys.remove(old_idx_and_key_pos);
-            debug_assert!(*old_key.ck.borrow() == k);
-        } else {
-            if self.key_to_value_and_index.len() == self.max_size {
-                // remove the least recently used
-                let oldest_idx_and_key = self.indexes_and_keys.remove(0);
-                let (_, oldest_idx_paired) = self
-                    .key_to_value_and_index
-                    .remove(oldest_idx_and_key.ck.borrow())
-                    .unwrap();
-                assert!(oldest_idx_and_key.idx == oldest_idx_paired);
-            }
+        if let Some((_old_v, slot)) = self.key_to_value_and_index.remove(&k) {
+            self.unlink(slot);
+            self.link_at_tail(slot);
+            let ck = CK::new(k);
+            self.key_to_value_and_index.insert(ck, (v, slot));
+            return;
+        }
+
+        if self.key_to_value_and_index.len() == self.max_size {
+            // remove the least recently used
+            let oldest_slot = self.head;
+            self.unlink(oldest_slot);
+            let oldest_ck = self.nodes[oldest_slot.to_usize()].ck.clone();
+            self.key_to_value_and_index.remove(oldest_ck.borrow());
+            self.free_slots.push(oldest_slot);
         }
-        let ck = CK::new(k);
-        self.key_to_value_and_index
-            .insert(ck.clone(), (v, self.next_insertion_index));
-        self.indexes_and_keys
-            .push(IndexAndKey::new(self.next_insertion_index, ck));
 
-        self.next_insertion_index.increment();
+        let ck = CK::new(k);
+        let slot = self.alloc_slot(ck.clone());
+        self.link_at_tail(slot);
+        self.key_to_value_and_index.insert(ck, (v, slot));
     }
 
     pub fn get(&mut self, k: &K) -> Option<&V> {
-        if let Some(value_and_index) = self.key_to_value_and_index.get_mut(k) {
-            let old_idx_and_key_pos = self
-                .indexes_and_keys
-                .binary_search_by_key(&value_and_index.1, |idx_and_key| idx_and_key.idx)
-                .unwrap();
+        let slot = self.key_to_value_and_index.get(k)?.1;
+        self.unlink(slot);
+        self.link_at_tail(slot);
+        // Re-look-up rather than reuse the borrow above, which would still be held across the
+        // `unlink`/`link_at_tail` calls (both take `&mut self`).
+        self.key_to_value_and_index.get(k).map(|(v, _slot)| v)
+    }
 
-            let existing_index_and_key = self.indexes_and_keys.remove(old_idx_and_key_pos);
-            debug_assert!(*existing_index_and_key.ck.borrow() == *k);
+    pub(crate) fn iter_lru_entries(&self) -> LruWalk<'_, K, V, I, CK, MOST_RECENT_FAST, RECYCLE> {
+        LruWalk {
+            cache: self,
+            cur: self.head,
+            forward: true,
+        }
+    }
 
-            self.indexes_and_keys.push(IndexAndKey::new(
-                self.next_insertion_index,
-                existing_index_and_key.ck,
-            ));
+    pub(crate) fn iter_mru_entries(&self) -> LruWalk<'_, K, V, I, CK, MOST_RECENT_FAST, RECYCLE> {
+        LruWalk {
+            cache: self,
+            cur: self.tail,
+            forward: false,
+        }
+    }
 
-            value_and_index.1 = self.next_insertion_index;
+    /// Iterates `(&K, &V)` from least- to most-recently-used, following the recency order rather
+    /// than `HashMap` bucket order.
+    pub fn iter_lru(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.iter_lru_entries()
+    }
 
-            self.next_insertion_index.increment();
-            return Some(&value_and_index.0);
-        } else {
+    /// Iterates `(&K, &V)` from most- to least-recently-used.
+    pub fn iter_mru(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.iter_mru_entries()
+    }
+}
+
+/// Iterator over `(&K, &V)` produced by [LRUCache::iter_lru_entries] / [LRUCache::iter_mru_entries],
+/// walking the recency list from `head`/`next` (LRU-to-MRU) or `tail`/`prev` (the reverse) without
+/// touching order.
+pub(crate) struct LruWalk<
+    'a,
+    K,
+    V,
+    I: InsertionIndex,
+    CK: CloneKey<K>,
+    const MOST_RECENT_FAST: bool,
+    const RECYCLE: bool,
+> {
+    cache: &'a LRUCache<K, V, I, CK, MOST_RECENT_FAST, RECYCLE>,
+    cur: I,
+    forward: bool,
+}
+impl<
+        'a,
+        K: Hash + Eq,
+        V,
+        I: InsertionIndex,
+        CK: CloneKey<K> + Hash + Eq,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+    > Iterator for LruWalk<'a, K, V, I, CK, MOST_RECENT_FAST, RECYCLE>
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cur == I::NIL {
             return None;
         }
+        let node = &self.cache.nodes[self.cur.to_usize()];
+        self.cur = if self.forward { node.next } else { node.prev };
+        self.cache
+            .key_to_value_and_index
+            // `CK: Borrow<K>` is in scope alongside the blanket `Borrow<T> for T`, so plain
+            // `.get(&node.ck)` leaves rustc to infer `Q=K` - this cache is keyed by `CK` itself.
+            .get::<CK>(&node.ck)
+            .map(|(v, _slot)| (node.ck.borrow(), v))
     }
 }
 
@@ -196,6 +267,54 @@ impl<
 mod tests {
     use super::*;
 
+    type TestCache<V> = LRUCache<String, V, u32, String, false, false>;
+
+    #[test]
+    fn put_then_get_returns_value() {
+        let mut cache: TestCache<i32> = LRUCache::new(2);
+        cache.put("a".to_string(), 1);
+        assert_eq!(cache.get(&"a".to_string()), Some(&1));
+    }
+
     #[test]
-    fn it_works() {}
+    fn get_promotes_and_lru_eviction_picks_least_recently_used() {
+        let mut cache: TestCache<i32> = LRUCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+        assert_eq!(cache.get(&"a".to_string()), Some(&1));
+        cache.put("c".to_string(), 3);
+
+        assert_eq!(cache.get(&"b".to_string()), None);
+        assert_eq!(cache.get(&"a".to_string()), Some(&1));
+        assert_eq!(cache.get(&"c".to_string()), Some(&3));
+    }
+
+    #[test]
+    fn put_overwrites_existing_key_without_growing() {
+        let mut cache: TestCache<i32> = LRUCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("a".to_string(), 2);
+        assert_eq!(cache.get(&"a".to_string()), Some(&2));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn iter_lru_and_iter_mru_walk_opposite_directions() {
+        let mut cache: TestCache<i32> = LRUCache::new(3);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+        cache.put("c".to_string(), 3);
+
+        let lru: Vec<_> = cache.iter_lru().map(|(k, v)| (k.clone(), *v)).collect();
+        let mru: Vec<_> = cache.iter_mru().map(|(k, v)| (k.clone(), *v)).collect();
+        assert_eq!(
+            lru,
+            vec![
+                ("a".to_string(), 1),
+                ("b".to_string(), 2),
+                ("c".to_string(), 3)
+            ]
+        );
+        assert_eq!(mru, lru.into_iter().rev().collect::<Vec<_>>());
+    }
 }
@@ -0,0 +1,337 @@
+//! A 128-bit-fingerprint variant of [crate::shared_hash::DhCache].
+//!
+//! `DhCache` stores a single 64-bit hash per entry but still falls back to comparing the full `K`
+//! (see `KeyAndIdx::eq`'s `debug_assert_eq!`), because a lone 64-bit hash has non-negligible
+//! collision risk for large keys. [FingerprintCache] instead fingerprints each key as two
+//! independent 64-bit hashes (computed with two distinct-seeded `RandomState`s) and treats two
+//! entries as equal iff both halves match - about 2^-128 collision probability - so equality never
+//! touches `K` at all. With `KS = `[`Dropped`], it goes further and doesn't store `K` either,
+//! saving that memory on large-key, value-only caches; equality is the same fingerprint compare
+//! in both modes. [crate::shared_hash::DhCache] remains the default, exact path.
+
+use super::InsertionIndex;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash, Hasher};
+use hash_injector::{Flags, SignalledInjectionBuildHasher};
+use std::collections::HashMap;
+use std::hash::RandomState;
+
+/// Two independent 64-bit hashes of the same key, treated as equal iff both halves match.
+#[derive(Clone, Copy)]
+struct Fingerprint {
+    lo: u64,
+    hi: u64,
+}
+impl Fingerprint {
+    fn new<K: Hash>(k: &K, build_lo: &RandomState, build_hi: &RandomState) -> Self {
+        let mut lo_hasher = build_lo.build_hasher();
+        k.hash(&mut lo_hasher);
+        let mut hi_hasher = build_hi.build_hasher();
+        k.hash(&mut hi_hasher);
+        Self {
+            lo: lo_hasher.finish(),
+            hi: hi_hasher.finish(),
+        }
+    }
+
+    /// Folds the two halves into one `u64`, the way rustc's hash combiners fold partial hashes,
+    /// so the backing `HashMap` still has something to bucket on. Equality always checks both
+    /// halves in full; this is for bucket placement only.
+    fn combined(&self) -> u64 {
+        self.lo.wrapping_mul(3).wrapping_add(self.hi)
+    }
+}
+impl PartialEq for Fingerprint {
+    fn eq(&self, other: &Self) -> bool {
+        self.lo == other.lo && self.hi == other.hi
+    }
+}
+impl Eq for Fingerprint {}
+
+/// Chooses whether a [FingerprintCache] keeps a copy of `K` alongside `V`, or relies solely on the
+/// fingerprint and drops `K` once computed. See [Stored] and [Dropped].
+pub trait KeyStorage<K>: Sized {
+    fn store(k: K) -> Self;
+    fn as_ref(&self) -> Option<&K>;
+}
+
+/// Keeps a copy of `K` alongside `V` (the default). Entries are still compared by fingerprint
+/// alone, and no [FingerprintCache] method hands the stored key back.
+pub struct Stored<K>(K);
+impl<K> KeyStorage<K> for Stored<K> {
+    fn store(k: K) -> Self {
+        Stored(k)
+    }
+    fn as_ref(&self) -> Option<&K> {
+        Some(&self.0)
+    }
+}
+
+/// Drops `K` once its fingerprint is computed; a zero-sized type, so entries cost only `V` plus
+/// the fingerprint. Only sound for value-only caches that never need the key back.
+pub struct Dropped;
+impl<K> KeyStorage<K> for Dropped {
+    fn store(_k: K) -> Self {
+        Dropped
+    }
+    fn as_ref(&self) -> Option<&K> {
+        None
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Idx<I: InsertionIndex, const F: Flags> {
+    fingerprint: Fingerprint,
+    idx: I,
+}
+impl<I: InsertionIndex, const F: Flags> Idx<I, F> {
+    fn new(idx: I, fingerprint: Fingerprint) -> Self {
+        Self { idx, fingerprint }
+    }
+}
+impl<I: InsertionIndex, const F: Flags> PartialEq for Idx<I, F> {
+    /// Unlike [crate::shared_hash]'s `Idx` (which compares the slot, since a by-key probe there
+    /// goes through its own `Key` type instead), equality here is the fingerprint only - there is
+    /// no key-comparison path to fall back on, by design.
+    fn eq(&self, other: &Self) -> bool {
+        self.fingerprint == other.fingerprint
+    }
+}
+impl<I: InsertionIndex, const F: Flags> Eq for Idx<I, F> {}
+impl<I: InsertionIndex, const F: Flags> Hash for Idx<I, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_injector::signal_inject_hash::<H, F>(state, self.fingerprint.combined());
+    }
+}
+
+struct KeyAndIdx<K, KS: KeyStorage<K>, I: InsertionIndex, const F: Flags> {
+    ks: KS,
+    idx: Idx<I, F>,
+    _phantom_key: core::marker::PhantomData<K>,
+}
+impl<K, KS: KeyStorage<K>, I: InsertionIndex, const F: Flags> KeyAndIdx<K, KS, I, F> {
+    fn new(ks: KS, idx: Idx<I, F>) -> Self {
+        Self {
+            ks,
+            idx,
+            _phantom_key: core::marker::PhantomData,
+        }
+    }
+}
+impl<K, KS: KeyStorage<K>, I: InsertionIndex, const F: Flags> Hash for KeyAndIdx<K, KS, I, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_injector::signal_inject_hash::<H, F>(state, self.idx.fingerprint.combined());
+    }
+}
+impl<K, KS: KeyStorage<K>, I: InsertionIndex, const F: Flags> PartialEq for KeyAndIdx<K, KS, I, F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.idx.fingerprint == other.idx.fingerprint
+    }
+}
+impl<K, KS: KeyStorage<K>, I: InsertionIndex, const F: Flags> Eq for KeyAndIdx<K, KS, I, F> {}
+impl<K, KS: KeyStorage<K>, I: InsertionIndex, const F: Flags> Borrow<Idx<I, F>>
+    for KeyAndIdx<K, KS, I, F>
+{
+    fn borrow(&self) -> &Idx<I, F> {
+        &self.idx
+    }
+}
+
+type SignalledBuildHasher<const F: Flags> =
+    SignalledInjectionBuildHasher<<RandomState as BuildHasher>::Hasher, RandomState, F>;
+
+struct Node<I: InsertionIndex> {
+    prev: I,
+    next: I,
+    fingerprint: Fingerprint,
+}
+impl<I: InsertionIndex> Node<I> {
+    fn new(fingerprint: Fingerprint) -> Self {
+        Self {
+            prev: I::NIL,
+            next: I::NIL,
+            fingerprint,
+        }
+    }
+}
+
+pub struct FingerprintCache<
+    K,
+    V,
+    I: InsertionIndex,
+    KS: KeyStorage<K> = Stored<K>,
+    const MOST_RECENT_FAST: bool = false,
+    const RECYCLE: bool = false,
+    const F: Flags = { Flags::EMPTY },
+> {
+    max_size: usize,
+    build_lo: RandomState,
+    build_hi: RandomState,
+    key_and_idx_to_value: HashMap<KeyAndIdx<K, KS, I, F>, V, SignalledBuildHasher<F>>,
+    nodes: Vec<Node<I>>,
+    free_slots: Vec<I>,
+    head: I,
+    tail: I,
+}
+
+impl<
+        K: Hash,
+        V,
+        I: InsertionIndex,
+        KS: KeyStorage<K>,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+        const F: Flags,
+    > FingerprintCache<K, V, I, KS, MOST_RECENT_FAST, RECYCLE, F>
+{
+    pub fn new(max_size: usize) -> Self {
+        assert!(I::accommodates(max_size));
+        let build_hasher = SignalledInjectionBuildHasher::new(RandomState::new());
+        Self {
+            max_size,
+            build_lo: RandomState::new(),
+            build_hi: RandomState::new(),
+            key_and_idx_to_value: HashMap::with_capacity_and_hasher(max_size, build_hasher),
+            nodes: Vec::with_capacity(max_size),
+            free_slots: Vec::new(),
+            head: I::NIL,
+            tail: I::NIL,
+        }
+    }
+
+    fn fingerprint_of(&self, k: &K) -> Fingerprint {
+        Fingerprint::new(k, &self.build_lo, &self.build_hi)
+    }
+
+    fn unlink(&mut self, slot: I) {
+        let (prev, next) = {
+            let node = &self.nodes[slot.to_usize()];
+            (node.prev, node.next)
+        };
+        if prev != I::NIL {
+            self.nodes[prev.to_usize()].next = next;
+        } else {
+            self.head = next;
+        }
+        if next != I::NIL {
+            self.nodes[next.to_usize()].prev = prev;
+        } else {
+            self.tail = prev;
+        }
+    }
+
+    fn link_at_tail(&mut self, slot: I) {
+        let old_tail = self.tail;
+        {
+            let node = &mut self.nodes[slot.to_usize()];
+            node.prev = old_tail;
+            node.next = I::NIL;
+        }
+        if old_tail != I::NIL {
+            self.nodes[old_tail.to_usize()].next = slot;
+        } else {
+            self.head = slot;
+        }
+        self.tail = slot;
+    }
+
+    fn alloc_slot(&mut self, fingerprint: Fingerprint) -> I {
+        if let Some(slot) = self.free_slots.pop() {
+            self.nodes[slot.to_usize()] = Node::new(fingerprint);
+            slot
+        } else {
+            let slot = I::from_usize(self.nodes.len());
+            self.nodes.push(Node::new(fingerprint));
+            slot
+        }
+    }
+
+    fn evict_lru(&mut self) {
+        let oldest_slot = self.head;
+        self.unlink(oldest_slot);
+        let probe = Idx::new(oldest_slot, self.nodes[oldest_slot.to_usize()].fingerprint);
+        self.key_and_idx_to_value.remove_entry(&probe).unwrap();
+        self.free_slots.push(oldest_slot);
+    }
+
+    fn promote(&mut self, slot: I, fingerprint: Fingerprint, ks: KS, v: V) {
+        self.unlink(slot);
+        self.link_at_tail(slot);
+        self.nodes[slot.to_usize()].fingerprint = fingerprint;
+
+        let idx = Idx::new(slot, fingerprint);
+        self.key_and_idx_to_value.insert(KeyAndIdx::new(ks, idx), v);
+    }
+
+    fn insert_new(&mut self, fingerprint: Fingerprint, ks: KS, v: V) {
+        if self.key_and_idx_to_value.len() == self.max_size {
+            self.evict_lru();
+        }
+
+        let slot = self.alloc_slot(fingerprint);
+        self.link_at_tail(slot);
+
+        let idx = Idx::new(slot, fingerprint);
+        self.key_and_idx_to_value.insert(KeyAndIdx::new(ks, idx), v);
+    }
+
+    pub fn put(&mut self, k: K, v: V) {
+        debug_assert!(self.key_and_idx_to_value.len() <= self.max_size);
+
+        let fingerprint = self.fingerprint_of(&k);
+        let probe = Idx::new(I::ZERO, fingerprint);
+
+        if let Some((old_key_and_idx, _old_v)) = self.key_and_idx_to_value.remove_entry(&probe) {
+            self.promote(old_key_and_idx.idx.idx, fingerprint, KS::store(k), v);
+        } else {
+            self.insert_new(fingerprint, KS::store(k), v);
+        }
+    }
+
+    pub fn get(&mut self, k: &K) -> Option<&V> {
+        debug_assert!(self.key_and_idx_to_value.len() <= self.max_size);
+
+        let fingerprint = self.fingerprint_of(k);
+        let probe = Idx::new(I::ZERO, fingerprint);
+
+        if let Some((key_and_idx, v)) = self.key_and_idx_to_value.remove_entry(&probe) {
+            let slot = key_and_idx.idx.idx;
+            self.promote(slot, fingerprint, key_and_idx.ks, v);
+            self.key_and_idx_to_value.get(&Idx::new(slot, fingerprint))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn put_then_get_returns_value_with_key_stored() {
+        let mut cache: FingerprintCache<String, i32, u32> = FingerprintCache::new(2);
+        cache.put("a".to_string(), 1);
+        assert_eq!(cache.get(&"a".to_string()), Some(&1));
+    }
+
+    #[test]
+    fn lru_eviction_picks_least_recently_used() {
+        let mut cache: FingerprintCache<String, i32, u32> = FingerprintCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+        assert_eq!(cache.get(&"a".to_string()), Some(&1));
+        cache.put("c".to_string(), 3);
+
+        assert_eq!(cache.get(&"b".to_string()), None);
+        assert_eq!(cache.get(&"a".to_string()), Some(&1));
+        assert_eq!(cache.get(&"c".to_string()), Some(&3));
+    }
+
+    #[test]
+    fn dropped_key_storage_still_round_trips_the_value() {
+        let mut cache: FingerprintCache<String, i32, u32, Dropped> = FingerprintCache::new(2);
+        cache.put("a".to_string(), 1);
+        assert_eq!(cache.get(&"a".to_string()), Some(&1));
+    }
+}
@@ -0,0 +1,353 @@
+//! A `no_std`, allocation-free LRU cache backed by a fixed-capacity arena.
+//!
+//! Unlike [crate::double_key::LRUCache] / [crate::shared_hash::DhCache], [FixedLruCache] never
+//! touches the heap: its `N` slots are part of the value itself (an array, not a `Vec`), so
+//! capacity is bounded and memory use is deterministic, the way `heapless` containers are sized
+//! for embedded targets. Eviction recycles a slot instead of freeing it. Keys are looked up via an
+//! `IDX_CAP`-bucket open-addressing index table rather than a linear scan over `N`.
+//!
+//! This module itself depends only on `core`, so it's usable from a `no_std` binary that pulls in
+//! this crate with `default-features = false, features = ["no_std"]`.
+
+use super::InsertionIndex;
+use core::hash::{Hash, Hasher};
+
+#[derive(Clone, Copy)]
+struct Node<I> {
+    prev: I,
+    next: I,
+}
+
+/// A minimal FNV-1a hasher, so the index table below can hash keys without pulling in
+/// `std::hash::RandomState` (unavailable in `no_std`). Deterministic rather than randomly seeded -
+/// fine here, since this is an in-process, fixed-capacity cache with no untrusted-keyspace DoS
+/// surface to defend against.
+struct FnvHasher(u64);
+impl FnvHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+}
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(Self::PRIME);
+        }
+    }
+}
+
+fn hash_of<K: Hash + ?Sized>(k: &K) -> u64 {
+    let mut hasher = FnvHasher::new();
+    k.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// One bucket of [FixedLruCache]'s index table, probed linearly starting at `hash_of(k) % IDX_CAP`.
+#[derive(Clone, Copy)]
+enum Bucket<I> {
+    /// Never occupied since the last rebuild; a probe stops here, since anything placed after an
+    /// `Empty` bucket in its own probe sequence would instead have stopped here first.
+    Empty,
+    /// Vacated by [FixedLruCache::index_remove]. A probe must keep going past one of these - the
+    /// key it's looking for may have been placed further along the same probe sequence - but it's
+    /// available for [FixedLruCache::index_insert] to reuse.
+    Tombstone,
+    Occupied(I),
+}
+
+/// `N` fixed-capacity slots, each either empty or holding one live `(K, V)`, addressed via an
+/// `IDX_CAP`-bucket open-addressing index table. `IDX_CAP` is a separate const generic rather than
+/// derived from `N`, since sizing it off `N` needs `generic_const_exprs` (unstable as of this
+/// writing); callers should pick `IDX_CAP` a few times `N` for a low load factor, the way
+/// `heapless`'s `FnvIndexMap` asks callers to size its own backing table.
+pub struct FixedLruCache<K, V, I: InsertionIndex, const N: usize, const IDX_CAP: usize> {
+    len: usize,
+    slots: [Option<(K, V)>; N],
+    /// Recency-list links, indexed in parallel with `slots`; valid only where `slots[i]` is
+    /// `Some`.
+    links: [Node<I>; N],
+    /// Open-addressing index: `hash_of(k) % IDX_CAP` to the slot holding that key.
+    index: [Bucket<I>; IDX_CAP],
+    /// Tombstone count, tracked so [Self::maybe_compact_index] knows when the table is too full
+    /// of them to guarantee a probe still terminates, and must rebuild from `slots` first.
+    tombstones: usize,
+    head: I,
+    tail: I,
+}
+
+impl<K, V, I: InsertionIndex, const N: usize, const IDX_CAP: usize>
+    FixedLruCache<K, V, I, N, IDX_CAP>
+{
+    pub fn new() -> Self {
+        assert!(I::accommodates(N));
+        assert!(
+            IDX_CAP > N,
+            "IDX_CAP must leave room for at least one Empty bucket, or a probe for a missing key would never terminate"
+        );
+        Self {
+            len: 0,
+            slots: core::array::from_fn(|_| None),
+            links: [Node {
+                prev: I::NIL,
+                next: I::NIL,
+            }; N],
+            index: [Bucket::Empty; IDX_CAP],
+            tombstones: 0,
+            head: I::NIL,
+            tail: I::NIL,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Unlinks the node at `slot` from wherever it currently sits in the list.
+    fn unlink(&mut self, slot: I) {
+        let Node { prev, next } = self.links[slot.to_usize()];
+        if prev != I::NIL {
+            self.links[prev.to_usize()].next = next;
+        } else {
+            self.head = next;
+        }
+        if next != I::NIL {
+            self.links[next.to_usize()].prev = prev;
+        } else {
+            self.tail = prev;
+        }
+    }
+
+    /// Links `slot` in as the most-recently-used (the tail) of the list.
+    fn link_at_tail(&mut self, slot: I) {
+        let old_tail = self.tail;
+        self.links[slot.to_usize()] = Node {
+            prev: old_tail,
+            next: I::NIL,
+        };
+        if old_tail != I::NIL {
+            self.links[old_tail.to_usize()].next = slot;
+        } else {
+            self.head = slot;
+        }
+        self.tail = slot;
+    }
+
+    /// Finds the slot (if any) currently holding `k`.
+    fn find_slot(&self, k: &K) -> Option<I>
+    where
+        K: Hash + Eq,
+    {
+        let mut i = hash_of(k) as usize % IDX_CAP;
+        for _ in 0..IDX_CAP {
+            match self.index[i] {
+                Bucket::Empty => return None,
+                Bucket::Tombstone => {}
+                Bucket::Occupied(slot) => {
+                    if self.slots[slot.to_usize()]
+                        .as_ref()
+                        .is_some_and(|(slot_k, _)| slot_k == k)
+                    {
+                        return Some(slot);
+                    }
+                }
+            }
+            i = (i + 1) % IDX_CAP;
+        }
+        None
+    }
+
+    /// Places `slot` (known not to already be indexed) into the first `Empty` or `Tombstone`
+    /// bucket on `k`'s probe sequence. Only correct to call right after
+    /// [Self::maybe_compact_index], which guarantees a non-`Occupied` bucket is reachable.
+    fn index_insert(&mut self, k: &K, slot: I)
+    where
+        K: Hash,
+    {
+        let mut i = hash_of(k) as usize % IDX_CAP;
+        loop {
+            match self.index[i] {
+                Bucket::Empty => {
+                    self.index[i] = Bucket::Occupied(slot);
+                    return;
+                }
+                Bucket::Tombstone => {
+                    self.index[i] = Bucket::Occupied(slot);
+                    self.tombstones -= 1;
+                    return;
+                }
+                Bucket::Occupied(_) => {}
+            }
+            i = (i + 1) % IDX_CAP;
+        }
+    }
+
+    /// Tombstones the bucket holding `slot` on `k`'s probe sequence.
+    fn index_remove(&mut self, k: &K, slot: I)
+    where
+        K: Hash,
+    {
+        let mut i = hash_of(k) as usize % IDX_CAP;
+        loop {
+            match self.index[i] {
+                Bucket::Occupied(s) if s == slot => {
+                    self.index[i] = Bucket::Tombstone;
+                    self.tombstones += 1;
+                    return;
+                }
+                Bucket::Empty => unreachable!("slot must be reachable on k's own probe sequence"),
+                _ => {}
+            }
+            i = (i + 1) % IDX_CAP;
+        }
+    }
+
+    /// Rebuilds the index table from scratch (dropping every tombstone) if it's gotten full
+    /// enough that an `Empty` bucket might not remain reachable on some probe sequence. `O(N)`,
+    /// amortized over however many tombstones accumulated since the last rebuild.
+    fn maybe_compact_index(&mut self)
+    where
+        K: Hash,
+    {
+        if self.len + self.tombstones + 1 < IDX_CAP {
+            return;
+        }
+        self.index = [Bucket::Empty; IDX_CAP];
+        self.tombstones = 0;
+        let mut cur = self.head;
+        while cur != I::NIL {
+            let slot_i = cur.to_usize();
+            if let Some((k, _)) = &self.slots[slot_i] {
+                Self::reinsert(&mut self.index, k, cur);
+            }
+            cur = self.links[slot_i].next;
+        }
+    }
+
+    /// Same probing as [Self::index_insert], but free of `&mut self` so [Self::maybe_compact_index]
+    /// can call it while `self.slots` is still borrowed.
+    fn reinsert(index: &mut [Bucket<I>; IDX_CAP], k: &K, slot: I)
+    where
+        K: Hash,
+    {
+        let mut i = hash_of(k) as usize % IDX_CAP;
+        loop {
+            if let Bucket::Empty | Bucket::Tombstone = index[i] {
+                index[i] = Bucket::Occupied(slot);
+                return;
+            }
+            i = (i + 1) % IDX_CAP;
+        }
+    }
+
+    pub fn put(&mut self, k: K, v: V)
+    where
+        K: Hash + Eq,
+    {
+        if let Some(slot) = self.find_slot(&k) {
+            self.unlink(slot);
+            self.link_at_tail(slot);
+            self.slots[slot.to_usize()] = Some((k, v));
+            return;
+        }
+
+        let slot = if self.len == N {
+            // remove the least recently used
+            let oldest = self.head;
+            self.unlink(oldest);
+            if let Some((old_k, _)) = self.slots[oldest.to_usize()].take() {
+                self.index_remove(&old_k, oldest);
+            }
+            oldest
+        } else {
+            let slot = I::from_usize(self.len);
+            self.len += 1;
+            slot
+        };
+        self.link_at_tail(slot);
+        self.maybe_compact_index();
+        self.index_insert(&k, slot);
+        self.slots[slot.to_usize()] = Some((k, v));
+    }
+
+    pub fn get(&mut self, k: &K) -> Option<&V>
+    where
+        K: Hash + Eq,
+    {
+        let slot = self.find_slot(k)?;
+        self.unlink(slot);
+        self.link_at_tail(slot);
+        self.slots[slot.to_usize()].as_ref().map(|(_, v)| v)
+    }
+}
+
+impl<K, V, I: InsertionIndex, const N: usize, const IDX_CAP: usize> Default
+    for FixedLruCache<K, V, I, N, IDX_CAP>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestCache<V> = FixedLruCache<&'static str, V, u32, 2, 8>;
+
+    #[test]
+    fn put_then_get_returns_value() {
+        let mut cache: TestCache<i32> = FixedLruCache::new();
+        cache.put("a", 1);
+        assert_eq!(cache.get(&"a"), Some(&1));
+    }
+
+    #[test]
+    fn get_promotes_and_lru_eviction_picks_least_recently_used() {
+        let mut cache: TestCache<i32> = FixedLruCache::new();
+        cache.put("a", 1);
+        cache.put("b", 2);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        cache.put("c", 3);
+
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        assert_eq!(cache.get(&"c"), Some(&3));
+    }
+
+    #[test]
+    fn len_and_is_empty_track_live_entries_up_to_capacity() {
+        let mut cache: TestCache<i32> = FixedLruCache::new();
+        assert!(cache.is_empty());
+        cache.put("a", 1);
+        cache.put("b", 2);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.capacity(), 2);
+        cache.put("c", 3);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn repeated_eviction_past_idx_cap_still_finds_live_keys() {
+        // A tiny IDX_CAP relative to the number of distinct keys cycled through forces
+        // maybe_compact_index to kick in repeatedly; every key must still be findable afterwards.
+        let mut cache: FixedLruCache<i32, i32, u32, 2, 3> = FixedLruCache::new();
+        for i in 0..20 {
+            cache.put(i, i * 10);
+            assert_eq!(cache.get(&i), Some(&(i * 10)));
+        }
+    }
+}
@@ -1,33 +1,80 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+//! # `no_std`
+//!
+//! This crate is `#![no_std]` unless the (default-on) `std` feature is enabled: [double_key],
+//! [fingerprint], [sharded], [shared_hash], and `persist`'s `serde`/`rkyv` impls all hard-depend on
+//! `std::collections::HashMap` / `Rc` / `Arc` / `Mutex` / `RandomState` / `Instant`, so they're
+//! gated behind `std` and simply don't exist in a `default-features = false` build. [fixed] is the
+//! one module that depends only on `core` and so is usable either way, gated behind its own
+//! `no_std` feature instead (turning it on doesn't imply turning `std` off).
+
 use core::hash::Hash;
 
-pub mod shared_hash;
+#[cfg(feature = "std")]
+pub mod cached_fn;
+#[cfg(feature = "std")]
 pub mod double_key;
+#[cfg(feature = "std")]
+pub mod fingerprint;
+#[cfg(feature = "no_std")]
+pub mod fixed;
+#[cfg(feature = "std")]
+mod persist;
+#[cfg(feature = "std")]
+pub mod sharded;
+#[cfg(feature = "std")]
+pub mod shared_hash;
 
+#[cfg(all(feature = "std", feature = "rkyv"))]
+pub use persist::FrozenCache;
+
+/// Also doubles as the slot address type for the intrusive recency list: every live entry owns
+/// exactly one `I`-addressed slot, and [InsertionIndex::NIL] is the sentinel used by an empty
+/// list (so a cache's capacity is bounded by `I::MAX`, not `I::MAX + 1`).
 trait InsertionIndex: Ord + Copy + Hash {
     const ZERO: Self;
     /** Maximum index. */
     const MAX: Self;
+    /// Sentinel "no slot" address, used for list `head`/`tail`/`prev`/`next` links.
+    const NIL: Self;
     fn increment(&mut self);
     fn accommodates(size: usize) -> bool;
+    fn to_usize(self) -> usize;
+    fn from_usize(i: usize) -> Self;
 }
 impl InsertionIndex for u8 {
     const ZERO: Self = 0;
     const MAX: Self = u8::MAX;
+    const NIL: Self = u8::MAX;
     fn increment(&mut self) {
         *self += 1;
     }
     fn accommodates(size: usize) -> bool {
-        Self::MAX as usize >= size
+        // NIL is reserved, so one fewer slot is usable.
+        Self::MAX as usize > size
+    }
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+    fn from_usize(i: usize) -> Self {
+        i as Self
     }
 }
 impl InsertionIndex for u16 {
     const ZERO: Self = 0;
     const MAX: Self = u16::MAX;
+    const NIL: Self = u16::MAX;
     fn increment(&mut self) {
         *self += 1;
     }
     fn accommodates(size: usize) -> bool {
-        Self::MAX as usize >= size
+        Self::MAX as usize > size
+    }
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+    fn from_usize(i: usize) -> Self {
+        i as Self
     }
 }
 #[cfg(any(target_pointer_width = "16", target_pointer_width = "32"))]
@@ -46,29 +93,50 @@ type UsizeAndU64 = u64;
 impl InsertionIndex for u32 {
     const ZERO: Self = 0;
     const MAX: Self = u32::MAX;
+    const NIL: Self = u32::MAX;
     fn increment(&mut self) {
         *self += 1;
     }
     fn accommodates(size: usize) -> bool {
-        Self::MAX as UsizeAndU32 >= size as UsizeAndU32
+        Self::MAX as UsizeAndU32 > size as UsizeAndU32
+    }
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+    fn from_usize(i: usize) -> Self {
+        i as Self
     }
 }
 impl InsertionIndex for u64 {
     const ZERO: Self = 0;
     const MAX: Self = u64::MAX;
+    const NIL: Self = u64::MAX;
     fn increment(&mut self) {
         *self += 1;
     }
     fn accommodates(size: usize) -> bool {
-        Self::MAX as UsizeAndU64 >= size as UsizeAndU64
+        Self::MAX as UsizeAndU64 > size as UsizeAndU64
+    }
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+    fn from_usize(i: usize) -> Self {
+        i as Self
     }
 }
 impl InsertionIndex for u128 {
     const ZERO: Self = 0;
     const MAX: Self = u128::MAX;
+    const NIL: Self = u128::MAX;
     fn increment(&mut self) {
         *self += 1;
     }
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+    fn from_usize(i: usize) -> Self {
+        i as Self
+    }
     fn accommodates(size: usize) -> bool {
         Self::MAX >= size as Self
     }
@@ -79,5 +147,17 @@ mod tests {
     use super::*;
 
     #[test]
-    fn it_works() {}
+    fn u32_accommodates_up_to_but_not_including_its_nil_sentinel() {
+        assert!(u32::accommodates(u32::MAX as usize - 1));
+        assert!(!u32::accommodates(u32::MAX as usize));
+    }
+
+    #[test]
+    fn u8_increment_and_index_conversions_round_trip() {
+        let mut i = u8::ZERO;
+        i.increment();
+        i.increment();
+        assert_eq!(i, 2);
+        assert_eq!(u8::from_usize(i.to_usize()), i);
+    }
 }
@@ -0,0 +1,198 @@
+//! Optional cross-process persistence for the cache types. Following hashbrown's lead, `serde`
+//! and `rkyv` support is implemented entirely as external trait impls gated behind features,
+//! rather than built into [crate::double_key] / [crate::shared_hash].
+
+#[cfg(feature = "serde")]
+mod serde_support {
+    use crate::double_key::{CloneKey, LRUCache};
+    use crate::shared_hash::DhCache;
+    use crate::InsertionIndex;
+    use core::hash::Hash;
+    use hash_injector::Flags;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    impl<K, V, I, CK, const MOST_RECENT_FAST: bool, const RECYCLE: bool> Serialize
+        for LRUCache<K, V, I, CK, MOST_RECENT_FAST, RECYCLE>
+    where
+        K: Hash + Eq + Serialize,
+        V: Serialize,
+        I: InsertionIndex,
+        CK: CloneKey<K> + Hash + Eq,
+    {
+        /// Serializes `(max_size, entries)`, with `entries` oldest-to-newest, so that replaying
+        /// them through [LRUCache::put] on deserialization reproduces identical eviction
+        /// behavior.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let entries: Vec<(&K, &V)> = self.iter_lru_entries().collect();
+            (self.max_size(), entries).serialize(serializer)
+        }
+    }
+
+    impl<'de, K, V, I, CK, const MOST_RECENT_FAST: bool, const RECYCLE: bool> Deserialize<'de>
+        for LRUCache<K, V, I, CK, MOST_RECENT_FAST, RECYCLE>
+    where
+        K: Hash + Eq + Deserialize<'de>,
+        V: Deserialize<'de>,
+        I: InsertionIndex,
+        CK: CloneKey<K> + Hash + Eq,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (max_size, entries): (usize, Vec<(K, V)>) = Deserialize::deserialize(deserializer)?;
+            let mut cache = Self::new(max_size);
+            for (k, v) in entries {
+                cache.put(k, v);
+            }
+            Ok(cache)
+        }
+    }
+
+    impl<K, V, I, const MOST_RECENT_FAST: bool, const RECYCLE: bool, const F: Flags> Serialize
+        for DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+    where
+        K: Hash + Eq + Serialize,
+        V: Serialize,
+        I: InsertionIndex,
+    {
+        /// Serializes `(max_size, ttl, entries)`, with `entries` oldest-to-newest. The stored
+        /// `u64` hashes are seed-dependent (see [crate::shared_hash]'s `RandomState`-keyed
+        /// hasher), so only `K`/`V` are serialized; deserialization re-hashes each key under a
+        /// freshly constructed `RandomState` instead of trusting persisted hashes.
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let entries: Vec<(&K, &V)> = self.iter_lru_entries().collect();
+            (self.max_size(), self.ttl(), entries).serialize(serializer)
+        }
+    }
+
+    impl<'de, K, V, I, const MOST_RECENT_FAST: bool, const RECYCLE: bool, const F: Flags>
+        Deserialize<'de> for DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+    where
+        K: Hash + Eq + Deserialize<'de>,
+        V: Deserialize<'de>,
+        I: InsertionIndex,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (max_size, ttl, entries): (usize, Option<Duration>, Vec<(K, V)>) =
+                Deserialize::deserialize(deserializer)?;
+            let mut cache = match ttl {
+                Some(ttl) => Self::with_ttl(max_size, ttl),
+                None => Self::new(max_size),
+            };
+            for (k, v) in entries {
+                cache.put(k, v);
+            }
+            Ok(cache)
+        }
+    }
+}
+
+/// Deliberately narrower than the `serde` support above: neither [LRUCache] nor [DhCache]
+/// implements `rkyv::Archive` itself (eviction bookkeeping - the recency list, free slots, the
+/// hash-injection machinery - has no meaningful archived form to `mmap` and look up from directly).
+/// Instead, [LRUCache::to_frozen] / [DhCache::to_frozen] clone out a [FrozenCache] snapshot of just
+/// the entries, and that's what's actually `rkyv`-archivable.
+#[cfg(feature = "rkyv")]
+mod rkyv_support {
+    use crate::double_key::{CloneKey, LRUCache};
+    use crate::shared_hash::DhCache;
+    use crate::InsertionIndex;
+    use core::hash::Hash;
+    use hash_injector::Flags;
+
+    /// A read-only, oldest-to-newest snapshot of a cache's contents, meant for `rkyv`: archive it
+    /// once, then `mmap` and look entries up directly from the archived bytes without
+    /// deserializing, and without paying for any eviction bookkeeping.
+    #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+    pub struct FrozenCache<K, V> {
+        /// Oldest (least-recently-used) first.
+        entries: Vec<(K, V)>,
+    }
+
+    impl<K: Eq, V> FrozenCache<K, V> {
+        /// Most-recently-used first, since that's the more likely hit.
+        pub fn get(&self, k: &K) -> Option<&V> {
+            self.entries
+                .iter()
+                .rev()
+                .find(|(entry_k, _)| entry_k == k)
+                .map(|(_, v)| v)
+        }
+    }
+
+    impl<K: Eq + rkyv::Archive, V: rkyv::Archive> ArchivedFrozenCache<K, V>
+    where
+        rkyv::Archived<K>: PartialEq<K>,
+    {
+        /// Same lookup as [FrozenCache::get], but directly over the archived (zero-copy) form.
+        pub fn get(&self, k: &K) -> Option<&rkyv::Archived<V>> {
+            self.entries
+                .iter()
+                .rev()
+                .find(|entry| entry.0 == *k)
+                .map(|entry| &entry.1)
+        }
+    }
+
+    impl<
+            K: Clone + Hash + Eq,
+            V: Clone,
+            I: InsertionIndex,
+            CK: CloneKey<K> + Hash + Eq,
+            const MOST_RECENT_FAST: bool,
+            const RECYCLE: bool,
+        > LRUCache<K, V, I, CK, MOST_RECENT_FAST, RECYCLE>
+    {
+        /// Clones out a read-only, `rkyv`-archivable snapshot of the current contents.
+        pub fn to_frozen(&self) -> FrozenCache<K, V> {
+            FrozenCache {
+                entries: self
+                    .iter_lru_entries()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            }
+        }
+    }
+
+    impl<
+            K: Clone + Hash + Eq,
+            V: Clone,
+            I: InsertionIndex,
+            const MOST_RECENT_FAST: bool,
+            const RECYCLE: bool,
+            const F: Flags,
+        > DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+    {
+        /// Clones out a read-only, `rkyv`-archivable snapshot of the current contents.
+        pub fn to_frozen(&self) -> FrozenCache<K, V> {
+            FrozenCache {
+                entries: self
+                    .iter_lru_entries()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rkyv")]
+pub use rkyv_support::FrozenCache;
+
+#[cfg(all(test, feature = "rkyv"))]
+mod tests {
+    use crate::shared_hash::DhCache;
+    use hash_injector::Flags;
+
+    #[test]
+    fn to_frozen_round_trips_via_get_in_mru_order() {
+        let mut cache: DhCache<String, i32, u32, false, false, { Flags::EMPTY }> =
+            DhCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("a".to_string(), 2);
+        cache.put("b".to_string(), 3);
+
+        let frozen = cache.to_frozen();
+        assert_eq!(frozen.get(&"a".to_string()), Some(&2));
+        assert_eq!(frozen.get(&"b".to_string()), Some(&3));
+        assert_eq!(frozen.get(&"missing".to_string()), None);
+    }
+}
@@ -0,0 +1,144 @@
+//! A thread-safe cache built out of independent [DhCache] shards, so callers get a single shared
+//! cache instead of having to wrap [crate::shared_hash::DhCache] in one big lock themselves.
+
+use crate::shared_hash::DhCache;
+use crate::InsertionIndex;
+use core::hash::Hash;
+use hash_injector::Flags;
+use std::hash::RandomState;
+use std::sync::Mutex;
+
+/// Partitions keys across `shard_count` independent [DhCache]s, each guarded by its own [Mutex],
+/// so contention scales with shard count rather than serializing on one lock.
+pub struct ShardedLruCache<
+    K,
+    V,
+    I: InsertionIndex,
+    const MOST_RECENT_FAST: bool,
+    const RECYCLE: bool,
+    const F: Flags,
+> {
+    shards: Vec<Mutex<DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>>>,
+}
+
+impl<
+        K: Hash + Eq,
+        V,
+        I: InsertionIndex,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+        const F: Flags,
+    > ShardedLruCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+{
+    /// `max_size` is the cache's total capacity, split evenly across `shard_count` shards (each
+    /// shard gets `max_size / shard_count`, rounded up so every shard has room for at least one
+    /// entry).
+    pub fn new(max_size: usize, shard_count: usize) -> Self {
+        assert!(shard_count > 0);
+        let per_shard = max_size.div_ceil(shard_count).max(1);
+        // Every shard's table is seeded from a clone of the same `RandomState`, so hashing `k`
+        // once (via any one shard's [DhCache::hash_of]) to route also yields the exact hash the
+        // chosen shard would compute internally - see [Self::route].
+        let hasher = RandomState::new();
+        Self {
+            shards: (0..shard_count)
+                .map(|_| Mutex::new(DhCache::new_with_hasher(per_shard, None, hasher.clone())))
+                .collect(),
+        }
+    }
+
+    /// Hashes `k` once - via [DhCache::hash_of] on an arbitrary shard, since every shard's table
+    /// shares the same `RandomState` and so would compute the same hash - returning both the shard
+    /// to route to and the hash itself, so the caller can hand it straight to that shard instead
+    /// of it hashing `k` again.
+    fn route(&self, k: &K) -> (usize, u64) {
+        let hash = self.shards[0].lock().unwrap().hash_of(k);
+        let shard = ((hash >> (u64::BITS - 8)) as usize) % self.shards.len();
+        (shard, hash)
+    }
+
+    pub fn put(&self, k: K, v: V) {
+        let (shard, hash) = self.route(&k);
+        self.shards[shard].lock().unwrap().put_prehashed(k, v, hash);
+    }
+
+    /// Returns a clone of the value, promoting the entry to most-recently-used. Cloning (rather
+    /// than returning `&V`) is what lets this take `&self`: the value can't outlive the shard's
+    /// [Mutex] guard. Requires `K: Clone` (on top of [Self::put]'s bounds) so the key can be
+    /// handed to the shard by value for [DhCache::get_prehashed] to reuse the routing hash.
+    pub fn get(&self, k: &K) -> Option<V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let (shard, hash) = self.route(k);
+        self.shards[shard]
+            .lock()
+            .unwrap()
+            .get_prehashed(k.clone(), hash)
+            .cloned()
+    }
+
+    pub fn get_or_insert_with(&self, k: K, f: impl FnOnce() -> V) -> V
+    where
+        V: Clone,
+    {
+        let (shard, hash) = self.route(&k);
+        self.shards[shard]
+            .lock()
+            .unwrap()
+            .entry_prehashed(k, hash)
+            .or_insert_with(f)
+            .clone()
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<
+        K: Hash + Eq + Send,
+        V: Send,
+        I: InsertionIndex + Send,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+        const F: Flags,
+    > ShardedLruCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+{
+    /// Bulk-populates the cache in parallel, following hashbrown's rayon extension pattern.
+    pub fn par_extend<T>(&self, iter: T)
+    where
+        T: rayon::iter::IntoParallelIterator<Item = (K, V)>,
+    {
+        use rayon::iter::ParallelIterator;
+        iter.into_par_iter().for_each(|(k, v)| self.put(k, v));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestCache = ShardedLruCache<String, i32, u32, false, false, { Flags::EMPTY }>;
+
+    #[test]
+    fn put_then_get_returns_value() {
+        let cache: TestCache = ShardedLruCache::new(8, 4);
+        cache.put("a".to_string(), 1);
+        assert_eq!(cache.get(&"a".to_string()), Some(1));
+    }
+
+    #[test]
+    fn get_or_insert_with_only_computes_on_a_miss() {
+        let cache: TestCache = ShardedLruCache::new(8, 4);
+        assert_eq!(cache.get_or_insert_with("a".to_string(), || 1), 1);
+        assert_eq!(cache.get_or_insert_with("a".to_string(), || 99), 1);
+    }
+
+    #[test]
+    fn shard_count_exceeding_max_size_does_not_panic_and_still_stores_entries() {
+        // 8 shards for a cache of size 4 would make `max_size / shard_count` round down to 0;
+        // the flooring in `new` must keep every shard able to hold at least one entry.
+        let cache: TestCache = ShardedLruCache::new(4, 8);
+        cache.put("a".to_string(), 1);
+        assert_eq!(cache.get(&"a".to_string()), Some(1));
+    }
+}
@@ -5,6 +5,7 @@ use core::mem;
 use hash_injector::{Flags, SignalledInjectionBuildHasher};
 use std::collections::HashMap;
 use std::hash::RandomState;
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Copy)]
 struct Idx<I: InsertionIndex, const F: Flags> {
@@ -25,28 +26,6 @@ impl<I: InsertionIndex, const F: Flags> PartialEq for Idx<I, F> {
     }
 }
 impl<I: InsertionIndex, const F: Flags> Eq for Idx<I, F> {}
-impl<I: InsertionIndex, const F: Flags> PartialOrd for Idx<I, F> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.idx.partial_cmp(&other.idx)
-    }
-    fn ge(&self, other: &Self) -> bool {
-        self.idx.ge(&other.idx)
-    }
-    fn gt(&self, other: &Self) -> bool {
-        self.idx.gt(&other.idx)
-    }
-    fn le(&self, other: &Self) -> bool {
-        self.idx.le(&other.idx)
-    }
-    fn lt(&self, other: &Self) -> bool {
-        self.idx.lt(&other.idx)
-    }
-}
-impl<I: InsertionIndex, const F: Flags> Ord for Idx<I, F> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.idx.cmp(&other.idx)
-    }
-}
 impl<I: InsertionIndex + Hash, const F: Flags> Hash for Idx<I, F> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         hash_injector::signal_inject_hash::<H, F>(state, self.hash);
@@ -121,35 +100,83 @@ impl<K, I: InsertionIndex, const F: Flags> Borrow<Idx<I, F>> for KeyAndIdx<K, I,
 }
 
 // @TODO move PartialEq, Eq and Hash to #[derive()]
-/// Needed, because we can't implement both `Borrow<Idx<I>>` and `Borrow<K>` for `KeyAndIdx<K, I>`,
-/// as they could conflict.
+/// Needed, because we can't implement both `Borrow<Idx<I>>` and `Borrow<Q>` for `KeyAndIdx<K, I>`,
+/// as they could conflict. Generic over `Q` (not just `K`) so that [DhCache::get] and friends can
+/// probe with any borrowed form of `K` (e.g. `&str` for a `DhCache<String, ..>`), not just `&K`
+/// itself - `Q = K` is simply the case every caller used before this existed.
 #[repr(transparent)]
-struct Kwrap<K> {
-    k: K,
+struct Kwrap<Q: ?Sized> {
+    q: Q,
 }
-impl<K: PartialEq> PartialEq for Kwrap<K> {
+impl<Q: ?Sized + PartialEq> PartialEq for Kwrap<Q> {
     fn eq(&self, other: &Self) -> bool {
-        self.k == other.k
+        self.q == other.q
     }
     fn ne(&self, other: &Self) -> bool {
-        self.k != other.k
+        self.q != other.q
     }
 }
-impl<K: Eq> Eq for Kwrap<K> {}
-impl<K: Hash> Hash for Kwrap<K> {
+impl<Q: ?Sized + Eq> Eq for Kwrap<Q> {}
+impl<Q: ?Sized + Hash> Hash for Kwrap<Q> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.k.hash(state)
+        self.q.hash(state)
     }
 }
 
-impl<'a, K, I: InsertionIndex, const F: Flags> Borrow<Kwrap<K>> for KeyAndIdx<K, I, F> {
-    fn borrow(&self) -> &Kwrap<K> {
-        unsafe { mem::transmute(&self.key.k) }
+impl<K: Borrow<Q>, Q: ?Sized, I: InsertionIndex, const F: Flags> Borrow<Kwrap<Q>>
+    for KeyAndIdx<K, I, F>
+{
+    fn borrow(&self) -> &Kwrap<Q> {
+        unsafe { mem::transmute(self.key.k.borrow()) }
     }
 }
 
 type SignalledBuildHasher<const F: Flags> =
     SignalledInjectionBuildHasher<<RandomState as BuildHasher>::Hasher, RandomState, F>;
+
+/// Wraps a stored value with the `Instant` it was last touched (inserted or fetched). Backs
+/// [DhCache::with_ttl]'s expiration: an entry is stale once `ttl` has passed since its most
+/// recent touch, measured from last access rather than from insertion, so a hot entry never
+/// expires while it's actively being used - the same "touch" that already promotes recency here
+/// also resets the TTL clock.
+struct Stamped<V> {
+    v: V,
+    last_touch: Instant,
+}
+impl<V> Stamped<V> {
+    fn new(v: V) -> Self {
+        Self {
+            v,
+            last_touch: Instant::now(),
+        }
+    }
+}
+
+/// A node of the intrusive recency list, addressed by `I`. The map entry keyed by the same `I`
+/// slot (see [Idx::idx]) stays the source of truth for `hash`; this copy exists so that evicting
+/// the list head doesn't require a map traversal to rebuild the [Idx] needed to remove it.
+struct Node<I: InsertionIndex> {
+    prev: I,
+    next: I,
+    hash: u64,
+}
+impl<I: InsertionIndex> Node<I> {
+    fn new(hash: u64) -> Self {
+        Self {
+            prev: I::NIL,
+            next: I::NIL,
+            hash,
+        }
+    }
+}
+
+/// `RECYCLE` is a vestige of this type's old monotonic-insertion-index design, which addressed
+/// entries by an ever-growing counter and so needed a story for what happens when that counter
+/// hits `I::MAX`. Slots are drawn from a bounded arena instead now - `max_size` of them, recycled
+/// via `free_slots` - so a slot index never exceeds `max_size`, and [Self::new]'s
+/// `I::accommodates(max_size)` assertion is already the hard precondition that rules out the
+/// overflow `RECYCLE` was meant to handle. It's kept only so this type's generic signature matches
+/// the rest of the crate's cache types; nothing in this module reads it.
 pub struct DhCache<
     K,
     V,
@@ -159,102 +186,811 @@ pub struct DhCache<
     const F: Flags,
 > {
     max_size: usize,
-    next_insertion_index: I,
-    key_and_idx_to_value: HashMap<KeyAndIdx<K, I, F>, V, SignalledBuildHasher<F>>,
-    /// Always sorted.
-    indexes: Vec<Idx<I, F>>,
+    /// `None` means entries never expire (the default, via [Self::new]). See [Self::with_ttl].
+    ttl: Option<Duration>,
+    key_and_idx_to_value: HashMap<KeyAndIdx<K, I, F>, Stamped<V>, SignalledBuildHasher<F>>,
+    /// Slab of recency-list nodes, addressed by slot (the same `I` stored in each entry's [Idx]).
+    /// Touching an entry unlinks and relinks its node at `tail` in O(1); eviction pops `head`.
+    nodes: Vec<Node<I>>,
+    /// Vacated slots available for reuse before growing `nodes`.
+    free_slots: Vec<I>,
+    head: I,
+    tail: I,
 }
 
 impl<
-    K: Hash + Eq,
-    V,
-    I: InsertionIndex,
-    const MOST_RECENT_FAST: bool,
-    const RECYCLE: bool,
-    const F: Flags,
-> DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+        K: Hash + Eq,
+        V,
+        I: InsertionIndex,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+        const F: Flags,
+    > DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>
 {
     pub fn new(max_size: usize) -> Self {
+        Self::new_with_ttl(max_size, None)
+    }
+
+    /// Like [Self::new], but entries older than `ttl` since their last touch (insertion, or a
+    /// promoting access such as [Self::get]/[Self::get_mut]/[Self::entry]) are treated as absent.
+    /// The promoting lookups evict such an entry lazily; [Self::peek] and [Self::contains_key]
+    /// only report it absent, and neither evicts it nor resets its clock. Call
+    /// [Self::purge_expired] to sweep stale entries that haven't been evicted yet; until then,
+    /// they still count against `max_size`.
+    pub fn with_ttl(max_size: usize, ttl: Duration) -> Self {
+        Self::new_with_ttl(max_size, Some(ttl))
+    }
+
+    fn new_with_ttl(max_size: usize, ttl: Option<Duration>) -> Self {
+        Self::new_with_hasher(max_size, ttl, RandomState::new())
+    }
+
+    /// Like [Self::new_with_ttl], but seeded from a caller-supplied `RandomState` instead of a
+    /// fresh one - lets [crate::sharded::ShardedLruCache] seed every shard's table from the same
+    /// `RandomState` it routes with, so a key is hashed once and the result reused for both,
+    /// instead of the shard hashing it again independently.
+    pub(crate) fn new_with_hasher(
+        max_size: usize,
+        ttl: Option<Duration>,
+        random_state: RandomState,
+    ) -> Self {
+        // Also the precondition that makes `RECYCLE` a no-op here: as long as every slot index
+        // stays within `I`'s range, the free-list can recycle slots forever without the monotonic
+        // counter overflow `RECYCLE` was meant to handle.
         assert!(I::accommodates(max_size));
 
-        let random_state = RandomState::new();
         let build_hasher = SignalledInjectionBuildHasher::new(random_state);
         Self {
             max_size,
-            next_insertion_index: I::ZERO,
+            ttl,
             key_and_idx_to_value: HashMap::with_capacity_and_hasher(max_size, build_hasher),
-            indexes: Vec::with_capacity(max_size),
+            nodes: Vec::with_capacity(max_size),
+            free_slots: Vec::new(),
+            head: I::NIL,
+            tail: I::NIL,
         }
     }
 
-    pub fn put(&mut self, k: K, v: V) {
-        debug_assert!(self.key_and_idx_to_value.len() <= self.max_size);
-        debug_assert_eq!(self.key_and_idx_to_value.len(), self.indexes.len());
+    pub(crate) fn max_size(&self) -> usize {
+        self.max_size
+    }
 
-        let key = Key::new_from_hasher(k, self.key_and_idx_to_value.hasher().build_hasher());
+    pub(crate) fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
 
-        if let Some((old_key_and_idx, _old_v)) = self.key_and_idx_to_value.remove_entry(&key) {
-            let old_idx_and_key_pos = self.indexes.binary_search(&old_key_and_idx.idx).unwrap();
-            // We always remove the old entry, even if the storage is not full (to our capacity)
-            // yet. We could store an Option, and set it to None, which would save the shifting of
-            // the rest of items. However, that would help only while storage is not full. But, a
-            // cache is beneficial/intended to use once it gets full, so we keep it simple.
-            self.indexes.remove(old_idx_and_key_pos);
+    pub(crate) fn len(&self) -> usize {
+        self.key_and_idx_to_value.len()
+    }
+
+    fn is_expired(&self, stamped: &Stamped<V>) -> bool {
+        match self.ttl {
+            Some(ttl) => stamped.last_touch.elapsed() > ttl,
+            None => false,
+        }
+    }
+
+    /// Unlinks the node at `slot` from wherever it currently sits in the list.
+    fn unlink(&mut self, slot: I) {
+        let (prev, next) = {
+            let node = &self.nodes[slot.to_usize()];
+            (node.prev, node.next)
+        };
+        if prev != I::NIL {
+            self.nodes[prev.to_usize()].next = next;
         } else {
-            if self.key_and_idx_to_value.len() == self.max_size {
-                // remove the least recently used
-                let oldest_idx = self.indexes.remove(0);
-
-                #[cfg(debug_assertions)]
-                {} //@TODO
-                let (oldest_key_and_idx, _oldest_value) =
-                    self.key_and_idx_to_value.remove_entry(&oldest_idx).unwrap();
-            }
+            self.head = next;
+        }
+        if next != I::NIL {
+            self.nodes[next.to_usize()].prev = prev;
+        } else {
+            self.tail = prev;
         }
-        let idx = Idx::new(self.next_insertion_index, key.hash);
+    }
+
+    /// Links `slot` in as the most-recently-used (the tail) of the list.
+    fn link_at_tail(&mut self, slot: I) {
+        let old_tail = self.tail;
+        {
+            let node = &mut self.nodes[slot.to_usize()];
+            node.prev = old_tail;
+            node.next = I::NIL;
+        }
+        if old_tail != I::NIL {
+            self.nodes[old_tail.to_usize()].next = slot;
+        } else {
+            self.head = slot;
+        }
+        self.tail = slot;
+    }
+
+    /// Allocates a slot for an entry hashing to `hash`, reusing a vacated one if available.
+    fn alloc_slot(&mut self, hash: u64) -> I {
+        if let Some(slot) = self.free_slots.pop() {
+            self.nodes[slot.to_usize()] = Node::new(hash);
+            slot
+        } else {
+            let slot = I::from_usize(self.nodes.len());
+            self.nodes.push(Node::new(hash));
+            slot
+        }
+    }
+
+    /// Evicts the least-recently-used entry. Caller must ensure the cache is non-empty.
+    fn evict_lru(&mut self) {
+        self.pop_lru().unwrap();
+    }
+
+    /// Re-inserts an entry already known to occupy `slot` (a hit), promoting it to most-recently
+    /// used, and returns the slot the entry now lives in.
+    fn promote(&mut self, slot: I, key: Key<K, F>, v: V) -> I {
+        self.unlink(slot);
+        self.link_at_tail(slot);
+        self.nodes[slot.to_usize()].hash = key.hash;
 
+        let idx = Idx::new(slot, key.hash);
         let key_and_idx = KeyAndIdx::new(key, idx);
-        self.key_and_idx_to_value.insert(key_and_idx, v);
+        self.key_and_idx_to_value
+            .insert(key_and_idx, Stamped::new(v));
 
-        self.indexes.push(idx);
+        slot
+    }
 
-        self.next_insertion_index.increment();
+    /// Inserts a new entry (a miss), evicting the least-recently used one first if at capacity,
+    /// and returns the slot the entry was placed in.
+    fn insert_new(&mut self, key: Key<K, F>, v: V) -> I {
+        if self.key_and_idx_to_value.len() == self.max_size {
+            self.evict_lru();
+        }
+
+        let slot = self.alloc_slot(key.hash);
+        self.link_at_tail(slot);
+
+        let idx = Idx::new(slot, key.hash);
+        let key_and_idx = KeyAndIdx::new(key, idx);
+        self.key_and_idx_to_value
+            .insert(key_and_idx, Stamped::new(v));
+
+        slot
+    }
+
+    pub fn put(&mut self, k: K, v: V) {
+        let key = Key::new_from_hasher(k, self.key_and_idx_to_value.hasher().build_hasher());
+        self.put_with_key(key, v);
+    }
+
+    /// Like [Self::put], but `hash` is `k`'s already-computed [Self::hash_of] hash, so `k` isn't
+    /// hashed a second time - lets [crate::sharded::ShardedLruCache] reuse the hash it just used
+    /// to route to this shard, instead of this cache hashing `k` again on top of that.
+    pub(crate) fn put_prehashed(&mut self, k: K, v: V, hash: u64) {
+        self.put_with_key(Key::new(k, hash), v);
+    }
+
+    fn put_with_key(&mut self, key: Key<K, F>, v: V) {
+        debug_assert!(self.key_and_idx_to_value.len() <= self.max_size);
+
+        if let Some((old_key_and_idx, _old_stamped)) = self.key_and_idx_to_value.remove_entry(&key)
+        {
+            self.promote(old_key_and_idx.idx.idx, key, v);
+        } else {
+            self.insert_new(key, v);
+        }
+    }
+
+    /// Hashes `k` exactly as this cache's internal table would, for callers (namely
+    /// [crate::sharded::ShardedLruCache]) that need to make a routing decision before reaching a
+    /// specific shard's cache, without paying for a second, independently-seeded hash of `k`.
+    pub(crate) fn hash_of<Q>(&self, k: &Q) -> u64
+    where
+        Q: Hash + ?Sized,
+    {
+        self.key_and_idx_to_value.hasher().hash_one(k)
     }
 
-    pub fn get(&mut self, k: &K) -> Option<&V> {
+    /// Looks up `k` in any borrowed form of `K` (e.g. `&str` for a `DhCache<String, ..>`),
+    /// promoting the entry to most-recently-used on a hit.
+    pub fn get<Q>(&mut self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         debug_assert!(self.key_and_idx_to_value.len() <= self.max_size);
-        debug_assert_eq!(self.key_and_idx_to_value.len(), self.indexes.len());
 
-        let k_wrap: &Kwrap<K> = unsafe { mem::transmute(k) };
+        let k_wrap: &Kwrap<Q> = unsafe { mem::transmute(k) };
 
-        if let Some((mut key_and_idx, v)) =
+        if let Some((key_and_idx, stamped)) =
             self.key_and_idx_to_value.remove_entry(k_wrap /*key*/)
         {
-            let old_idx_pos = self.indexes.binary_search(&key_and_idx.idx).unwrap();
+            let slot = key_and_idx.idx.idx;
+            if self.is_expired(&stamped) {
+                self.unlink(slot);
+                self.free_slots.push(slot);
+                return None;
+            }
+            let hash = key_and_idx.idx.hash;
+            self.promote(slot, key_and_idx.key, stamped.v);
 
-            self.indexes.remove(old_idx_pos);
+            // We don't perform .get(k) here, because that would re-calculate the hash.
+            self.key_and_idx_to_value
+                .get(&Idx::new(slot, hash))
+                .map(|stamped| &stamped.v)
+        } else {
+            None
+        }
+    }
 
-            //let key = Key::new_from_hasher(k, self.key_and_idx_to_value.hasher().build_hasher());
-            key_and_idx.idx.idx = self.next_insertion_index;
+    /// Like [Self::get], but `hash` is `k`'s already-computed [Self::hash_of] hash, and `k` must be
+    /// owned (mirroring [Self::entry]) rather than borrowed, so the lookup goes through the
+    /// hash-injection path instead of re-hashing `k`.
+    pub(crate) fn get_prehashed(&mut self, k: K, hash: u64) -> Option<&V> {
+        debug_assert!(self.key_and_idx_to_value.len() <= self.max_size);
 
-            let idx = Idx::new(self.next_insertion_index, key_and_idx.idx.hash);
-            self.indexes.push(idx);
+        let key = Key::new(k, hash);
+        if let Some((key_and_idx, stamped)) = self.key_and_idx_to_value.remove_entry(&key) {
+            let slot = key_and_idx.idx.idx;
+            if self.is_expired(&stamped) {
+                self.unlink(slot);
+                self.free_slots.push(slot);
+                return None;
+            }
+            self.promote(slot, key_and_idx.key, stamped.v);
+            self.key_and_idx_to_value
+                .get(&Idx::new(slot, hash))
+                .map(|stamped| &stamped.v)
+        } else {
+            None
+        }
+    }
 
-            self.key_and_idx_to_value.insert(key_and_idx, v);
-            self.next_insertion_index.increment();
+    /// Looks up `k` like [Self::get] (any borrowed form of `K`, promoting on a hit), but returns
+    /// a mutable reference.
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        debug_assert!(self.key_and_idx_to_value.len() <= self.max_size);
 
-            // We don't perform .get(k) here, because that would re-calculate the hash.
-            self.key_and_idx_to_value.get(&idx)
+        let k_wrap: &Kwrap<Q> = unsafe { mem::transmute(k) };
+
+        if let Some((key_and_idx, stamped)) = self.key_and_idx_to_value.remove_entry(k_wrap) {
+            let slot = key_and_idx.idx.idx;
+            if self.is_expired(&stamped) {
+                self.unlink(slot);
+                self.free_slots.push(slot);
+                return None;
+            }
+            let hash = key_and_idx.idx.hash;
+            self.promote(slot, key_and_idx.key, stamped.v);
+            self.key_and_idx_to_value
+                .get_mut(&Idx::new(slot, hash))
+                .map(|stamped| &mut stamped.v)
         } else {
             None
         }
     }
+
+    /// Looks up `k` without promoting the entry, so this can take `&self`. Unlike [Self::get],
+    /// does not touch the recency list, and an expired entry is simply reported absent rather
+    /// than lazily evicted.
+    pub fn peek<Q>(&self, k: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let k_wrap: &Kwrap<Q> = unsafe { mem::transmute(k) };
+        self.key_and_idx_to_value
+            .get(k_wrap)
+            .filter(|stamped| !self.is_expired(stamped))
+            .map(|stamped| &stamped.v)
+    }
+
+    /// Returns the least-recently-used entry without evicting it or touching order. `None` if the
+    /// cache is empty or the least-recently-used entry has expired.
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        if self.head == I::NIL {
+            return None;
+        }
+        let slot = self.head;
+        self.key_and_idx_to_value
+            .get_key_value(&Idx::new(slot, self.nodes[slot.to_usize()].hash))
+            .filter(|(_, stamped)| !self.is_expired(stamped))
+            .map(|(key_and_idx, stamped)| (&key_and_idx.key.k, &stamped.v))
+    }
+
+    /// Checks whether `k` is present and unexpired, without affecting recency order.
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let k_wrap: &Kwrap<Q> = unsafe { mem::transmute(k) };
+        self.key_and_idx_to_value
+            .get(k_wrap)
+            .is_some_and(|stamped| !self.is_expired(stamped))
+    }
+
+    /// Removes and returns the value for `k`, if present, regardless of whether it has expired.
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let k_wrap: &Kwrap<Q> = unsafe { mem::transmute(k) };
+        let (key_and_idx, stamped) = self.key_and_idx_to_value.remove_entry(k_wrap)?;
+        let slot = key_and_idx.idx.idx;
+        self.unlink(slot);
+        self.free_slots.push(slot);
+        Some(stamped.v)
+    }
+
+    /// Alias for [Self::remove], matching the `lru` crate's naming for callers migrating from it.
+    pub fn pop<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove(k)
+    }
+
+    /// Evicts and returns the least-recently-used entry, if any, regardless of whether it has
+    /// expired.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        if self.head == I::NIL {
+            return None;
+        }
+        let oldest_slot = self.head;
+        self.unlink(oldest_slot);
+        let oldest_idx = Idx::new(oldest_slot, self.nodes[oldest_slot.to_usize()].hash);
+        let (key_and_idx, stamped) = self.key_and_idx_to_value.remove_entry(&oldest_idx).unwrap();
+        self.free_slots.push(oldest_slot);
+        Some((key_and_idx.key.k, stamped.v))
+    }
+
+    /// Sweeps every expired entry, regardless of whether it's been looked up since expiring. A
+    /// no-op if this cache has no [Self::with_ttl] set.
+    pub fn purge_expired(&mut self) {
+        if self.ttl.is_none() {
+            return;
+        }
+
+        let mut cur = self.head;
+        while cur != I::NIL {
+            let slot = cur;
+            let hash = self.nodes[slot.to_usize()].hash;
+            cur = self.nodes[slot.to_usize()].next;
+
+            let idx = Idx::new(slot, hash);
+            let expired = self
+                .key_and_idx_to_value
+                .get(&idx)
+                .is_some_and(|stamped| self.is_expired(stamped));
+            if expired {
+                self.key_and_idx_to_value.remove(&idx);
+                self.unlink(slot);
+                self.free_slots.push(slot);
+            }
+        }
+    }
+
+    /// A raw-entry-style lookup that hashes `k` exactly once, whether it turns out to be a hit or
+    /// a miss. Mirrors hashbrown's raw-entry API rather than `std`'s owned-key `Entry`, since that
+    /// is the primitive this cache's hash-once design already depends on internally.
+    pub fn entry(&mut self, k: K) -> Entry<'_, K, V, I, MOST_RECENT_FAST, RECYCLE, F> {
+        let key = Key::new_from_hasher(k, self.key_and_idx_to_value.hasher().build_hasher());
+        self.entry_with_key(key)
+    }
+
+    /// Like [Self::entry], but `hash` is `k`'s already-computed [Self::hash_of] hash, so `k` isn't
+    /// hashed a second time.
+    pub(crate) fn entry_prehashed(
+        &mut self,
+        k: K,
+        hash: u64,
+    ) -> Entry<'_, K, V, I, MOST_RECENT_FAST, RECYCLE, F> {
+        self.entry_with_key(Key::new(k, hash))
+    }
+
+    fn entry_with_key(
+        &mut self,
+        key: Key<K, F>,
+    ) -> Entry<'_, K, V, I, MOST_RECENT_FAST, RECYCLE, F> {
+        // An expired occupied slot is evicted up front and treated as vacant below, rather than
+        // left for `VacantEntry::insert` to overwrite in place - that would orphan its recency-list
+        // node, since inserting over an existing map key doesn't go through `insert_new`'s slot
+        // allocation.
+        let is_expired = self
+            .key_and_idx_to_value
+            .get(&key)
+            .is_some_and(|stamped| self.is_expired(stamped));
+        if is_expired {
+            let (key_and_idx, _stamped) = self.key_and_idx_to_value.remove_entry(&key).unwrap();
+            let slot = key_and_idx.idx.idx;
+            self.unlink(slot);
+            self.free_slots.push(slot);
+        }
+
+        if let Some(key_and_idx) = self
+            .key_and_idx_to_value
+            .get_key_value(&key)
+            .map(|(k, _)| k)
+        {
+            let slot = key_and_idx.idx.idx;
+            Entry::Occupied(OccupiedEntry {
+                cache: self,
+                key,
+                slot,
+            })
+        } else {
+            Entry::Vacant(VacantEntry { cache: self, key })
+        }
+    }
+
+    /// Looks up `k`, computing its hash once regardless of hit or miss: on a hit, promotes the
+    /// entry to most-recently-used and returns it; on a miss, computes `f()`, stores it (evicting
+    /// the least-recently-used entry if at capacity), and returns the freshly stored value.
+    pub fn get_or_insert_with(&mut self, k: K, f: impl FnOnce() -> V) -> &mut V {
+        self.entry(k).or_insert_with(f)
+    }
+
+    pub(crate) fn iter_lru_entries(&self) -> LruWalk<'_, K, V, I, MOST_RECENT_FAST, RECYCLE, F> {
+        LruWalk {
+            cache: self,
+            cur: self.head,
+            forward: true,
+        }
+    }
+
+    pub(crate) fn iter_mru_entries(&self) -> LruWalk<'_, K, V, I, MOST_RECENT_FAST, RECYCLE, F> {
+        LruWalk {
+            cache: self,
+            cur: self.tail,
+            forward: false,
+        }
+    }
+
+    /// Iterates `(&K, &V)` from least- to most-recently-used, following the recency order rather
+    /// than `HashMap` bucket order.
+    pub fn iter_lru(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.iter_lru_entries()
+    }
+
+    /// Iterates `(&K, &V)` from most- to least-recently-used.
+    pub fn iter_mru(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.iter_mru_entries()
+    }
+}
+
+/// Iterator over `(&K, &V)` produced by [DhCache::iter_lru_entries] / [DhCache::iter_mru_entries],
+/// walking the recency list from `head`/`next` (LRU-to-MRU) or `tail`/`prev` (the reverse) without
+/// touching order. Each step re-derives the entry's [Idx] from the node's cached `hash` so the
+/// lookup goes through the same hash-injection path `get`/`put` use, rather than re-hashing `K`.
+pub(crate) struct LruWalk<
+    'a,
+    K,
+    V,
+    I: InsertionIndex,
+    const MOST_RECENT_FAST: bool,
+    const RECYCLE: bool,
+    const F: Flags,
+> {
+    cache: &'a DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>,
+    cur: I,
+    forward: bool,
+}
+impl<
+        'a,
+        K: Hash + Eq,
+        V,
+        I: InsertionIndex,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+        const F: Flags,
+    > Iterator for LruWalk<'a, K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Loops past (rather than stopping at) an expired entry: staleness is a property of one
+        // entry, not a reason to truncate the whole walk.
+        while self.cur != I::NIL {
+            let slot = self.cur;
+            let node = &self.cache.nodes[slot.to_usize()];
+            self.cur = if self.forward { node.next } else { node.prev };
+            if let Some((key_and_idx, stamped)) = self
+                .cache
+                .key_and_idx_to_value
+                .get_key_value(&Idx::new(slot, node.hash))
+            {
+                if !self.cache.is_expired(stamped) {
+                    return Some((&key_and_idx.key.k, &stamped.v));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// See [DhCache::entry].
+pub enum Entry<
+    'a,
+    K,
+    V,
+    I: InsertionIndex,
+    const MOST_RECENT_FAST: bool,
+    const RECYCLE: bool,
+    const F: Flags,
+> {
+    Occupied(OccupiedEntry<'a, K, V, I, MOST_RECENT_FAST, RECYCLE, F>),
+    Vacant(VacantEntry<'a, K, V, I, MOST_RECENT_FAST, RECYCLE, F>),
+}
+
+impl<
+        'a,
+        K: Hash + Eq,
+        V,
+        I: InsertionIndex,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+        const F: Flags,
+    > Entry<'a, K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+{
+    pub fn or_insert_with(self, f: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Entry::Occupied(occupied) => occupied.into_mut(),
+            Entry::Vacant(vacant) => vacant.insert(f()),
+        }
+    }
+}
+
+/// A view into an occupied entry, returned by [DhCache::entry]. Holds the already-computed key
+/// hash so promoting the entry never re-hashes `k`.
+pub struct OccupiedEntry<
+    'a,
+    K,
+    V,
+    I: InsertionIndex,
+    const MOST_RECENT_FAST: bool,
+    const RECYCLE: bool,
+    const F: Flags,
+> {
+    cache: &'a mut DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>,
+    key: Key<K, F>,
+    slot: I,
+}
+impl<
+        'a,
+        K: Hash + Eq,
+        V,
+        I: InsertionIndex,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+        const F: Flags,
+    > OccupiedEntry<'a, K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+{
+    /// Promotes the entry to most-recently-used and returns its value.
+    pub fn into_mut(self) -> &'a mut V {
+        let (_old_key_and_idx, stamped) = self
+            .cache
+            .key_and_idx_to_value
+            .remove_entry(&self.key)
+            .unwrap();
+        let hash = self.key.hash;
+        let slot = self.cache.promote(self.slot, self.key, stamped.v);
+        self.cache
+            .key_and_idx_to_value
+            .get_mut(&Idx::new(slot, hash))
+            .map(|stamped| &mut stamped.v)
+            .unwrap()
+    }
+}
+
+/// A view into a vacant entry, returned by [DhCache::entry]. Holds the already-computed key hash
+/// so inserting never re-hashes `k`.
+pub struct VacantEntry<
+    'a,
+    K,
+    V,
+    I: InsertionIndex,
+    const MOST_RECENT_FAST: bool,
+    const RECYCLE: bool,
+    const F: Flags,
+> {
+    cache: &'a mut DhCache<K, V, I, MOST_RECENT_FAST, RECYCLE, F>,
+    key: Key<K, F>,
+}
+impl<
+        'a,
+        K: Hash + Eq,
+        V,
+        I: InsertionIndex,
+        const MOST_RECENT_FAST: bool,
+        const RECYCLE: bool,
+        const F: Flags,
+    > VacantEntry<'a, K, V, I, MOST_RECENT_FAST, RECYCLE, F>
+{
+    /// The key this entry would be inserted under, for callers that need to compute `v` from `k`
+    /// before calling [Self::insert] (mirrors `std`'s `VacantEntry::key`).
+    pub fn key(&self) -> &K {
+        &self.key.k
+    }
+
+    /// Inserts `v` (evicting the least-recently-used entry first if at capacity) and returns it.
+    pub fn insert(self, v: V) -> &'a mut V {
+        let hash = self.key.hash;
+        let slot = self.cache.insert_new(self.key, v);
+        self.cache
+            .key_and_idx_to_value
+            .get_mut(&Idx::new(slot, hash))
+            .map(|stamped| &mut stamped.v)
+            .unwrap()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    type TestCache<V> = DhCache<String, V, u32, false, false, { Flags::EMPTY }>;
+
+    #[test]
+    fn put_then_get_returns_value() {
+        let mut cache: TestCache<i32> = DhCache::new(2);
+        cache.put("a".to_string(), 1);
+        assert_eq!(cache.get("a"), Some(&1));
+    }
+
+    #[test]
+    fn get_promotes_and_lru_eviction_picks_least_recently_used() {
+        let mut cache: TestCache<i32> = DhCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+        // Touch "a" so "b" becomes the least-recently-used.
+        assert_eq!(cache.get("a"), Some(&1));
+        cache.put("c".to_string(), 3);
+
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.get("c"), Some(&3));
+    }
+
+    #[test]
+    fn put_overwrites_existing_key_without_growing() {
+        let mut cache: TestCache<i32> = DhCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("a".to_string(), 2);
+        assert_eq!(cache.get("a"), Some(&2));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn entries_expire_after_ttl() {
+        let mut cache: DhCache<String, i32, u32, false, false, { Flags::EMPTY }> =
+            DhCache::with_ttl(2, Duration::from_millis(10));
+        cache.put("a".to_string(), 1);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(cache.get("a"), None);
+    }
+
+    #[test]
+    fn purge_expired_drops_stale_entries_without_a_lookup() {
+        let mut cache: DhCache<String, i32, u32, false, false, { Flags::EMPTY }> =
+            DhCache::with_ttl(2, Duration::from_millis(10));
+        cache.put("a".to_string(), 1);
+        std::thread::sleep(Duration::from_millis(50));
+        cache.purge_expired();
+        assert_eq!(cache.len(), 0);
+    }
+
     #[test]
-    fn it_works() {}
+    fn pop_lru_removes_the_oldest_entry() {
+        let mut cache: TestCache<i32> = DhCache::new(3);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+        assert_eq!(cache.pop_lru(), Some(("a".to_string(), 1)));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn entry_or_insert_with_only_computes_on_a_miss() {
+        let mut cache: TestCache<i32> = DhCache::new(2);
+        let mut calls = 0;
+        cache.get_or_insert_with("a".to_string(), || {
+            calls += 1;
+            1
+        });
+        cache.get_or_insert_with("a".to_string(), || {
+            calls += 1;
+            99
+        });
+        assert_eq!(calls, 1);
+        assert_eq!(cache.get("a"), Some(&1));
+    }
+
+    #[test]
+    fn iter_lru_and_iter_mru_walk_opposite_directions() {
+        let mut cache: TestCache<i32> = DhCache::new(3);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+        cache.put("c".to_string(), 3);
+
+        let lru: Vec<_> = cache.iter_lru().map(|(k, v)| (k.clone(), *v)).collect();
+        let mru: Vec<_> = cache.iter_mru().map(|(k, v)| (k.clone(), *v)).collect();
+        assert_eq!(
+            lru,
+            vec![
+                ("a".to_string(), 1),
+                ("b".to_string(), 2),
+                ("c".to_string(), 3)
+            ]
+        );
+        assert_eq!(mru, lru.into_iter().rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn peek_returns_the_value_without_promoting_recency() {
+        let mut cache: TestCache<i32> = DhCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+
+        assert_eq!(cache.peek("a"), Some(&1));
+        // "a" is still the least-recently-used, since `peek` must not have promoted it.
+        assert_eq!(cache.pop_lru(), Some(("a".to_string(), 1)));
+    }
+
+    #[test]
+    fn peek_lru_returns_the_oldest_entry_without_removing_it() {
+        let mut cache: TestCache<i32> = DhCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+
+        assert_eq!(cache.peek_lru(), Some((&"a".to_string(), &1)));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn get_mut_allows_in_place_mutation_and_promotes_recency() {
+        let mut cache: TestCache<i32> = DhCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+
+        *cache.get_mut("a").unwrap() += 10;
+        assert_eq!(cache.get("a"), Some(&11));
+        // "a" was touched twice now, so "b" is the least-recently-used.
+        assert_eq!(cache.pop_lru(), Some(("b".to_string(), 2)));
+    }
+
+    #[test]
+    fn pop_removes_and_returns_the_value_for_a_key() {
+        let mut cache: TestCache<i32> = DhCache::new(2);
+        cache.put("a".to_string(), 1);
+        assert_eq!(cache.pop("a"), Some(1));
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn contains_key_reflects_presence_without_affecting_recency() {
+        let mut cache: TestCache<i32> = DhCache::new(2);
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+
+        assert!(cache.contains_key("a"));
+        assert!(!cache.contains_key("z"));
+        // Neither check above should have promoted "a", so "a" is still the least-recently-used.
+        assert_eq!(cache.pop_lru(), Some(("a".to_string(), 1)));
+    }
+
+    #[test]
+    fn contains_key_and_remove_accept_a_borrowed_form_of_the_key() {
+        let mut cache: DhCache<String, i32, u32, false, false, { Flags::EMPTY }> = DhCache::new(2);
+        cache.put("a".to_string(), 1);
+
+        // `&str` here, not `&String`, to prove the `Borrow<Q>` plumbing works for a borrowed form
+        // other than `&K` itself.
+        let borrowed: &str = "a";
+        assert!(cache.contains_key(borrowed));
+        assert_eq!(cache.remove(borrowed), Some(1));
+        assert!(!cache.contains_key(borrowed));
+    }
 }